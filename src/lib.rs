@@ -1,8 +1,8 @@
-use std::collections::HashSet;
 use std::fmt;
+use std::str::FromStr;
 
 use anyhow::Result;
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use log::{debug, error, trace};
 use serde::Serialize;
 use sqlx::postgres::PgPool;
@@ -53,6 +53,35 @@ pub async fn get_res_by_numbers(pool: &PgPool, numbers: Vec<i32>) -> Result<Vec<
     result
 }
 
+/// Full-text searches post bodies using Postgres's `websearch_to_tsquery`, returning the
+/// matching post numbers ranked by relevance so they can flow through the existing numeric
+/// rendering path.
+pub async fn search_res_by_text(pool: &PgPool, keyword: &str, limit: i64) -> Result<Vec<i32>> {
+    let query = "SELECT no FROM res \
+                 WHERE to_tsvector('simple', main_text) @@ websearch_to_tsquery('simple', $1) \
+                 ORDER BY ts_rank(to_tsvector('simple', main_text), websearch_to_tsquery('simple', $1)) DESC \
+                 LIMIT $2";
+    debug!("search_res_by_text: keyword='{keyword}', limit={limit}");
+
+    let result: Result<Vec<(i32,)>, sqlx::Error> = sqlx::query_as(query)
+        .bind(keyword)
+        .bind(limit)
+        .fetch_all(pool)
+        .await;
+
+    match result {
+        Ok(rows) => {
+            let numbers: Vec<i32> = rows.into_iter().map(|(no,)| no).collect();
+            debug!("search_res_by_text: found {} matches", numbers.len());
+            Ok(numbers)
+        }
+        Err(e) => {
+            error!("search_res_by_text: error: {e:?}");
+            Err(e.into())
+        }
+    }
+}
+
 pub async fn get_max_post_number(pool: &PgPool) -> Result<i32> {
     let query = "SELECT MAX(no) FROM res";
     debug!("get_max_post_number: executing query");
@@ -64,17 +93,229 @@ pub async fn get_max_post_number(pool: &PgPool) -> Result<i32> {
     Ok(result)
 }
 
+/// Fetches every post whose `datetime` falls within `[start, end]`, the time-window counterpart
+/// to [`get_res_by_numbers`].
+pub async fn get_res_by_date_range(
+    pool: &PgPool,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<Vec<Res>> {
+    let query = "SELECT * FROM res WHERE datetime >= $1 AND datetime <= $2 ORDER BY no ASC";
+    debug!("get_res_by_date_range: start={start}, end={end}");
+
+    let result = sqlx::query_as::<_, Res>(query)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await
+        .map_err(Into::into);
+
+    match &result {
+        Ok(posts) => debug!("get_res_by_date_range: found {} posts", posts.len()),
+        Err(e) => error!("get_res_by_date_range: error: {e:?}"),
+    }
+
+    result
+}
+
+/// A Discord channel that has asked to be notified whenever new posts arrive, along with the
+/// post number it has already been caught up to.
+#[derive(Debug, FromRow)]
+pub struct Subscription {
+    pub channel_id: i64,
+    pub last_seen: i32,
+}
+
+pub async fn add_subscription(pool: &PgPool, channel_id: i64, last_seen: i32) -> Result<()> {
+    let query = "INSERT INTO subscriptions (channel_id, last_seen) VALUES ($1, $2) \
+                  ON CONFLICT (channel_id) DO NOTHING";
+    debug!("add_subscription: channel_id={channel_id}, last_seen={last_seen}");
+
+    let result = sqlx::query(query)
+        .bind(channel_id)
+        .bind(last_seen)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(Into::into);
+
+    if let Err(e) = &result {
+        error!("add_subscription: error: {e:?}");
+    }
+
+    result
+}
+
+pub async fn remove_subscription(pool: &PgPool, channel_id: i64) -> Result<()> {
+    let query = "DELETE FROM subscriptions WHERE channel_id = $1";
+    debug!("remove_subscription: channel_id={channel_id}");
+
+    let result = sqlx::query(query)
+        .bind(channel_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(Into::into);
+
+    if let Err(e) = &result {
+        error!("remove_subscription: error: {e:?}");
+    }
+
+    result
+}
+
+pub async fn get_subscriptions(pool: &PgPool) -> Result<Vec<Subscription>> {
+    let query = "SELECT channel_id, last_seen FROM subscriptions";
+    debug!("get_subscriptions: querying all subscriptions");
+
+    let result = sqlx::query_as::<_, Subscription>(query)
+        .fetch_all(pool)
+        .await
+        .map_err(Into::into);
+
+    match &result {
+        Ok(subs) => debug!("get_subscriptions: found {} subscriptions", subs.len()),
+        Err(e) => error!("get_subscriptions: error: {e:?}"),
+    }
+
+    result
+}
+
+pub async fn update_subscription_cursor(
+    pool: &PgPool,
+    channel_id: i64,
+    last_seen: i32,
+) -> Result<()> {
+    let query = "UPDATE subscriptions SET last_seen = $1 WHERE channel_id = $2";
+    debug!("update_subscription_cursor: channel_id={channel_id}, last_seen={last_seen}");
+
+    let result = sqlx::query(query)
+        .bind(last_seen)
+        .bind(channel_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(Into::into);
+
+    if let Err(e) = &result {
+        error!("update_subscription_cursor: error: {e:?}");
+    }
+
+    result
+}
+
+/// A cached perceptual (difference-)hash fingerprint for a post's oekaki image, stored as the
+/// bit pattern of a `u64` reinterpreted as a signed `bigint`.
+#[derive(Debug, FromRow)]
+pub struct OekakiHash {
+    pub post_no: i32,
+    pub hash: i64,
+}
+
+pub async fn get_oekaki_hash(pool: &PgPool, post_no: i32) -> Result<Option<i64>> {
+    let query = "SELECT hash FROM oekaki_hash WHERE post_no = $1";
+    debug!("get_oekaki_hash: post_no={post_no}");
+
+    let row: Option<(i64,)> = sqlx::query_as(query)
+        .bind(post_no)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|(hash,)| hash))
+}
+
+pub async fn store_oekaki_hash(pool: &PgPool, post_no: i32, hash: i64) -> Result<()> {
+    let query = "INSERT INTO oekaki_hash (post_no, hash) VALUES ($1, $2) \
+                  ON CONFLICT (post_no) DO UPDATE SET hash = EXCLUDED.hash";
+    debug!("store_oekaki_hash: post_no={post_no}, hash={hash}");
+
+    let result = sqlx::query(query)
+        .bind(post_no)
+        .bind(hash)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(Into::into);
+
+    if let Err(e) = &result {
+        error!("store_oekaki_hash: error: {e:?}");
+    }
+
+    result
+}
+
+pub async fn get_all_oekaki_hashes(pool: &PgPool) -> Result<Vec<OekakiHash>> {
+    let query = "SELECT post_no, hash FROM oekaki_hash";
+    debug!("get_all_oekaki_hashes: querying all fingerprints");
+
+    let result = sqlx::query_as::<_, OekakiHash>(query)
+        .fetch_all(pool)
+        .await
+        .map_err(Into::into);
+
+    match &result {
+        Ok(hashes) => debug!("get_all_oekaki_hashes: found {} fingerprints", hashes.len()),
+        Err(e) => error!("get_all_oekaki_hashes: error: {e:?}"),
+    }
+
+    result
+}
+
+/// Oekaki posts that don't have a row in `oekaki_hash` yet, so the backfill task can work
+/// through them a batch at a time.
+pub async fn get_unhashed_oekaki_posts(pool: &PgPool, limit: i64) -> Result<Vec<(i32, i32)>> {
+    let query = "SELECT res.no, res.oekaki_id FROM res \
+                 LEFT JOIN oekaki_hash ON oekaki_hash.post_no = res.no \
+                 WHERE res.oekaki_id IS NOT NULL AND oekaki_hash.post_no IS NULL \
+                 ORDER BY res.no ASC LIMIT $1";
+    debug!("get_unhashed_oekaki_posts: limit={limit}");
+
+    let result: Result<Vec<(i32, i32)>, sqlx::Error> =
+        sqlx::query_as(query).bind(limit).fetch_all(pool).await;
+
+    match &result {
+        Ok(rows) => debug!("get_unhashed_oekaki_posts: found {} posts", rows.len()),
+        Err(e) => error!("get_unhashed_oekaki_posts: error: {e:?}"),
+    }
+
+    result.map_err(Into::into)
+}
+
+/// Whether a range's right endpoint is included. Only meaningful when a spec carries an end
+/// value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeInclusion {
+    Inclusive,
+    Exclusive,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum RangeSpec {
-    Include(i32, Option<i32>),
-    Exclude(i32, Option<i32>),
-    IncludeFrom(i32), // For open-ended ranges like "123-"
-    ExcludeFrom(i32), // For open-ended exclusions like "^123-"
+    // (start, end, inclusion, step) — step defaults to 1 when `None`
+    Include(i32, Option<i32>, RangeInclusion, Option<i32>),
+    Exclude(i32, Option<i32>, RangeInclusion),
+    IncludeFrom(i32, Option<i32>), // For open-ended ranges like "123-", with an optional step
+    ExcludeFrom(i32),              // For open-ended exclusions like "^123-"
     // Relative references (? prefix) with digit count
-    RelativeInclude(i32, Option<i32>, usize), // (start, end, digit_count)
-    RelativeExclude(i32, Option<i32>, usize), // (start, end, digit_count)
-    RelativeIncludeFrom(i32, usize),          // (start, digit_count)
-    RelativeExcludeFrom(i32, usize),          // (start, digit_count)
+    RelativeInclude(i32, Option<i32>, usize, RangeInclusion, Option<i32>), // (start, end, digit_count, inclusion, step)
+    RelativeExclude(i32, Option<i32>, usize, RangeInclusion), // (start, end, digit_count, inclusion)
+    RelativeIncludeFrom(i32, usize, Option<i32>),             // (start, digit_count, step)
+    RelativeExcludeFrom(i32, usize),                          // (start, digit_count)
+}
+
+/// Locates the start/end separator in a range token, recognizing the inclusive `-` as well as
+/// the half-open `..` and `-<` spellings (e.g. `123..128` / `123-<128` excludes 128).
+fn find_range_separator(range_str: &str) -> Option<(usize, usize, RangeInclusion)> {
+    if let Some(pos) = range_str.find("..") {
+        return Some((pos, 2, RangeInclusion::Exclusive));
+    }
+    if let Some(pos) = range_str.find("-<") {
+        return Some((pos, 2, RangeInclusion::Exclusive));
+    }
+    if let Some(pos) = range_str.find('-') {
+        return Some((pos, 1, RangeInclusion::Inclusive));
+    }
+    None
 }
 
 pub fn parse_range_specifications(input: &str) -> Vec<RangeSpec> {
@@ -99,36 +340,55 @@ pub fn parse_range_specifications(input: &str) -> Vec<RangeSpec> {
             (false, range_str)
         };
 
-        if let Some(dash_pos) = range_str.find('-') {
-            let start_str = &range_str[..dash_pos];
-            let end_str = &range_str[dash_pos + 1..];
+        if let Some((sep_pos, sep_len, inclusion)) = find_range_separator(range_str) {
+            let start_str = &range_str[..sep_pos];
+            let (end_str, step_str) = split_step(&range_str[sep_pos + sep_len..]);
+
+            let step = match step_str {
+                Some(s) => match parse_step(s) {
+                    Ok(step) => Some(step),
+                    Err(_) => continue,
+                },
+                None => None,
+            };
 
             if let Ok(start) = start_str.parse::<i32>() {
                 let digit_count = if is_relative { start_str.len() } else { 0 };
 
                 if end_str.is_empty() {
-                    // Open-ended range like "123-"
+                    // Open-ended range like "123-", optionally strided like "123-:10"
                     match (is_relative, is_exclude) {
                         (true, true) => {
                             specs.push(RangeSpec::RelativeExcludeFrom(start, digit_count))
                         }
                         (true, false) => {
-                            specs.push(RangeSpec::RelativeIncludeFrom(start, digit_count))
+                            specs.push(RangeSpec::RelativeIncludeFrom(start, digit_count, step))
                         }
                         (false, true) => specs.push(RangeSpec::ExcludeFrom(start)),
-                        (false, false) => specs.push(RangeSpec::IncludeFrom(start)),
+                        (false, false) => specs.push(RangeSpec::IncludeFrom(start, step)),
                     }
                 } else if let Ok(end) = end_str.parse::<i32>() {
-                    // Closed range like "123-456"
+                    // Closed range like "123-456" or half-open "123..456", optionally strided
                     match (is_relative, is_exclude) {
-                        (true, true) => {
-                            specs.push(RangeSpec::RelativeExclude(start, Some(end), digit_count))
+                        (true, true) => specs.push(RangeSpec::RelativeExclude(
+                            start,
+                            Some(end),
+                            digit_count,
+                            inclusion,
+                        )),
+                        (true, false) => specs.push(RangeSpec::RelativeInclude(
+                            start,
+                            Some(end),
+                            digit_count,
+                            inclusion,
+                            step,
+                        )),
+                        (false, true) => {
+                            specs.push(RangeSpec::Exclude(start, Some(end), inclusion))
                         }
-                        (true, false) => {
-                            specs.push(RangeSpec::RelativeInclude(start, Some(end), digit_count))
+                        (false, false) => {
+                            specs.push(RangeSpec::Include(start, Some(end), inclusion, step))
                         }
-                        (false, true) => specs.push(RangeSpec::Exclude(start, Some(end))),
-                        (false, false) => specs.push(RangeSpec::Include(start, Some(end))),
                     }
                 }
             }
@@ -136,10 +396,28 @@ pub fn parse_range_specifications(input: &str) -> Vec<RangeSpec> {
             let digit_count = if is_relative { range_str.len() } else { 0 };
 
             match (is_relative, is_exclude) {
-                (true, true) => specs.push(RangeSpec::RelativeExclude(num, None, digit_count)),
-                (true, false) => specs.push(RangeSpec::RelativeInclude(num, None, digit_count)),
-                (false, true) => specs.push(RangeSpec::Exclude(num, None)),
-                (false, false) => specs.push(RangeSpec::Include(num, None)),
+                (true, true) => specs.push(RangeSpec::RelativeExclude(
+                    num,
+                    None,
+                    digit_count,
+                    RangeInclusion::Inclusive,
+                )),
+                (true, false) => specs.push(RangeSpec::RelativeInclude(
+                    num,
+                    None,
+                    digit_count,
+                    RangeInclusion::Inclusive,
+                    None,
+                )),
+                (false, true) => {
+                    specs.push(RangeSpec::Exclude(num, None, RangeInclusion::Inclusive))
+                }
+                (false, false) => specs.push(RangeSpec::Include(
+                    num,
+                    None,
+                    RangeInclusion::Inclusive,
+                    None,
+                )),
             }
         }
     }
@@ -147,12 +425,437 @@ pub fn parse_range_specifications(input: &str) -> Vec<RangeSpec> {
     specs
 }
 
+/// Why a single range-spec token was rejected by [`RangeSpec::from_str`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeParseError {
+    /// The token was empty, or became empty after stripping its `?`/`^` prefixes (e.g. `?^`).
+    EmptyToken,
+    /// A start/end component wasn't a valid integer.
+    InvalidNumber { token: String },
+    /// The range's end came before its start, e.g. `128-123`.
+    ReversedRange { start: i32, end: i32 },
+    /// A start or end value parsed to a negative post number.
+    NegativeResult,
+    /// A trailing `:N` stride wasn't a positive integer, e.g. `100-200:0`.
+    InvalidStep { token: String },
+}
+
+impl fmt::Display for RangeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RangeParseError::EmptyToken => write!(f, "empty range token"),
+            RangeParseError::InvalidNumber { token } => {
+                write!(f, "'{token}' is not a valid number")
+            }
+            RangeParseError::ReversedRange { start, end } => {
+                write!(f, "range end {end} comes before start {start}")
+            }
+            RangeParseError::NegativeResult => write!(f, "post numbers cannot be negative"),
+            RangeParseError::InvalidStep { token } => {
+                write!(
+                    f,
+                    "'{token}' is not a valid step (must be a positive integer)"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RangeParseError {}
+
+fn parse_range_number(token: &str) -> Result<i32, RangeParseError> {
+    let value: i32 = token.parse().map_err(|_| RangeParseError::InvalidNumber {
+        token: token.to_string(),
+    })?;
+
+    if value < 0 {
+        return Err(RangeParseError::NegativeResult);
+    }
+
+    Ok(value)
+}
+
+/// Splits a trailing `:N` stride off a range token, e.g. `"200:10"` -> `("200", Some("10"))`;
+/// `":10"` (the empty-end-plus-step form of an open-ended range) -> `("", Some("10"))`.
+fn split_step(range_str: &str) -> (&str, Option<&str>) {
+    match range_str.rfind(':') {
+        Some(pos) => (&range_str[..pos], Some(&range_str[pos + 1..])),
+        None => (range_str, None),
+    }
+}
+
+fn parse_step(token: &str) -> Result<i32, RangeParseError> {
+    let value: i32 = token.parse().map_err(|_| RangeParseError::InvalidStep {
+        token: token.to_string(),
+    })?;
+
+    if value <= 0 {
+        return Err(RangeParseError::InvalidStep {
+            token: token.to_string(),
+        });
+    }
+
+    Ok(value)
+}
+
+impl FromStr for RangeSpec {
+    type Err = RangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Err(RangeParseError::EmptyToken);
+        }
+
+        let (is_relative, range_str) = match trimmed.strip_prefix('?') {
+            Some(stripped) => (true, stripped),
+            None => (false, trimmed),
+        };
+
+        let (is_exclude, range_str) = match range_str.strip_prefix('^') {
+            Some(stripped) => (true, stripped),
+            None => (false, range_str),
+        };
+
+        if range_str.is_empty() {
+            return Err(RangeParseError::EmptyToken);
+        }
+
+        if let Some((sep_pos, sep_len, inclusion)) = find_range_separator(range_str) {
+            let start_str = &range_str[..sep_pos];
+            let (end_str, step_str) = split_step(&range_str[sep_pos + sep_len..]);
+            let step = step_str.map(parse_step).transpose()?;
+
+            let start = parse_range_number(start_str)?;
+            let digit_count = if is_relative { start_str.len() } else { 0 };
+
+            if end_str.is_empty() {
+                return Ok(match (is_relative, is_exclude) {
+                    (true, true) => RangeSpec::RelativeExcludeFrom(start, digit_count),
+                    (true, false) => RangeSpec::RelativeIncludeFrom(start, digit_count, step),
+                    (false, true) => RangeSpec::ExcludeFrom(start),
+                    (false, false) => RangeSpec::IncludeFrom(start, step),
+                });
+            }
+
+            let end = parse_range_number(end_str)?;
+            if end < start {
+                return Err(RangeParseError::ReversedRange { start, end });
+            }
+
+            Ok(match (is_relative, is_exclude) {
+                (true, true) => {
+                    RangeSpec::RelativeExclude(start, Some(end), digit_count, inclusion)
+                }
+                (true, false) => {
+                    RangeSpec::RelativeInclude(start, Some(end), digit_count, inclusion, step)
+                }
+                (false, true) => RangeSpec::Exclude(start, Some(end), inclusion),
+                (false, false) => RangeSpec::Include(start, Some(end), inclusion, step),
+            })
+        } else {
+            let num = parse_range_number(range_str)?;
+            let digit_count = if is_relative { range_str.len() } else { 0 };
+
+            Ok(match (is_relative, is_exclude) {
+                (true, true) => {
+                    RangeSpec::RelativeExclude(num, None, digit_count, RangeInclusion::Inclusive)
+                }
+                (true, false) => RangeSpec::RelativeInclude(
+                    num,
+                    None,
+                    digit_count,
+                    RangeInclusion::Inclusive,
+                    None,
+                ),
+                (false, true) => RangeSpec::Exclude(num, None, RangeInclusion::Inclusive),
+                (false, false) => RangeSpec::Include(num, None, RangeInclusion::Inclusive, None),
+            })
+        }
+    }
+}
+
+/// Like [`parse_range_specifications`], but instead of silently dropping a malformed token (a
+/// bad start value, a non-numeric end, a stray `^` with no number), collects every offending
+/// token's [`RangeParseError`] so the caller can report precisely what was wrong.
+pub fn parse_range_specifications_checked(
+    input: &str,
+) -> Result<Vec<RangeSpec>, Vec<RangeParseError>> {
+    let mut specs = Vec::new();
+    let mut errors = Vec::new();
+
+    for part in input.split(',') {
+        let trimmed = part.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match trimmed.parse::<RangeSpec>() {
+            Ok(spec) => specs.push(spec),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(specs)
+    } else {
+        Err(errors)
+    }
+}
+
+/// A time-window counterpart to [`RangeSpec`]: selects posts by `datetime` instead of `no`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateRangeSpec {
+    /// Both ends given explicitly, e.g. `2024-01-01..2024-02-01` or a bare day like `2024-01-01`
+    /// (expanded to that day's `00:00:00..23:59:59`).
+    Absolute(NaiveDateTime, NaiveDateTime),
+    /// Open-ended, e.g. `2024-01-01-`: everything from that moment up to now.
+    Since(NaiveDateTime),
+    /// A rolling window relative to now, e.g. `-7d` or `-24h`.
+    RelativePast(Duration),
+}
+
+impl DateRangeSpec {
+    /// Resolves this spec to a concrete `[start, end]` window, anchoring `Since`/`RelativePast`
+    /// against the current time.
+    pub fn resolve(&self) -> (NaiveDateTime, NaiveDateTime) {
+        match self {
+            DateRangeSpec::Absolute(start, end) => (*start, *end),
+            DateRangeSpec::Since(start) => (*start, Utc::now().naive_utc()),
+            DateRangeSpec::RelativePast(duration) => {
+                let now = Utc::now().naive_utc();
+                (now - *duration, now)
+            }
+        }
+    }
+}
+
+/// Why a date-range token was rejected by [`DateRangeSpec::from_str`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateRangeParseError {
+    EmptyToken,
+    InvalidDate {
+        token: String,
+    },
+    InvalidRelativeOffset {
+        token: String,
+    },
+    ReversedRange {
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    },
+}
+
+impl fmt::Display for DateRangeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DateRangeParseError::EmptyToken => write!(f, "empty date range token"),
+            DateRangeParseError::InvalidDate { token } => {
+                write!(
+                    f,
+                    "'{token}' is not a valid date (expected YYYY-MM-DD or YYYY-MM-DDThh:mm)"
+                )
+            }
+            DateRangeParseError::InvalidRelativeOffset { token } => {
+                write!(
+                    f,
+                    "'{token}' is not a valid relative offset (expected -<n>d, -<n>h or -<n>m)"
+                )
+            }
+            DateRangeParseError::ReversedRange { start, end } => {
+                write!(f, "range end {end} comes before start {start}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DateRangeParseError {}
+
+/// Parses a bare `YYYY-MM-DD` or `YYYY-MM-DDThh:mm` token into a concrete instant. A bare date
+/// is anchored to midnight unless `end_of_day` asks for `23:59:59` instead.
+fn parse_datetime_bound(
+    token: &str,
+    end_of_day: bool,
+) -> Result<NaiveDateTime, DateRangeParseError> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(token, "%Y-%m-%dT%H:%M") {
+        return Ok(dt);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+        let time = if end_of_day {
+            NaiveTime::from_hms_opt(23, 59, 59).unwrap()
+        } else {
+            NaiveTime::from_hms_opt(0, 0, 0).unwrap()
+        };
+        return Ok(NaiveDateTime::new(date, time));
+    }
+
+    Err(DateRangeParseError::InvalidDate {
+        token: token.to_string(),
+    })
+}
+
+/// Parses a `-<n>[dhm]` relative offset like `-7d` or `-24h`, returning `None` if `token` isn't
+/// even shaped like one (so the caller can fall through to absolute-date parsing).
+fn parse_relative_past(token: &str) -> Option<Result<Duration, DateRangeParseError>> {
+    let rest = token.strip_prefix('-')?;
+    if rest.is_empty() {
+        return Some(Err(DateRangeParseError::InvalidRelativeOffset {
+            token: token.to_string(),
+        }));
+    }
+
+    let (digits, unit) = rest.split_at(rest.len() - 1);
+    let Ok(amount) = digits.parse::<i64>() else {
+        return Some(Err(DateRangeParseError::InvalidRelativeOffset {
+            token: token.to_string(),
+        }));
+    };
+
+    let duration = match unit {
+        "d" => Duration::days(amount),
+        "h" => Duration::hours(amount),
+        "m" => Duration::minutes(amount),
+        _ => {
+            return Some(Err(DateRangeParseError::InvalidRelativeOffset {
+                token: token.to_string(),
+            }))
+        }
+    };
+
+    Some(Ok(duration))
+}
+
+impl FromStr for DateRangeSpec {
+    type Err = DateRangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Err(DateRangeParseError::EmptyToken);
+        }
+
+        if let Some(result) = parse_relative_past(trimmed) {
+            return result.map(DateRangeSpec::RelativePast);
+        }
+
+        if let Some((start_str, end_str)) = trimmed.split_once("..") {
+            let start = parse_datetime_bound(start_str, false)?;
+            let end = parse_datetime_bound(end_str, true)?;
+            if end < start {
+                return Err(DateRangeParseError::ReversedRange { start, end });
+            }
+            return Ok(DateRangeSpec::Absolute(start, end));
+        }
+
+        if let Some(stripped) = trimmed.strip_suffix('-') {
+            let start = parse_datetime_bound(stripped, false)?;
+            return Ok(DateRangeSpec::Since(start));
+        }
+
+        if trimmed.contains('T') {
+            let start = parse_datetime_bound(trimmed, false)?;
+            return Ok(DateRangeSpec::Since(start));
+        }
+
+        let start = parse_datetime_bound(trimmed, false)?;
+        let end = parse_datetime_bound(trimmed, true)?;
+        Ok(DateRangeSpec::Absolute(start, end))
+    }
+}
+
+/// Sorts `intervals` by their low end and fuses any that overlap or sit back-to-back (e.g.
+/// `(1, 5)` and `(6, 10)` fuse into `(1, 10)`), so a sweep over the result never has to reason
+/// about two intervals describing adjacent post numbers.
+fn merge_intervals(mut intervals: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+    intervals.sort_by_key(|&(lo, _)| lo);
+
+    let mut merged: Vec<(i32, i32)> = Vec::with_capacity(intervals.len());
+    for (lo, hi) in intervals {
+        match merged.last_mut() {
+            Some(last) if lo <= last.1.saturating_add(1) => last.1 = last.1.max(hi),
+            _ => merged.push((lo, hi)),
+        }
+    }
+    merged
+}
+
+/// Computes `include - exclude` (both already sorted and merged by [`merge_intervals`]) via a
+/// single linear sweep, splitting an include interval wherever an exclude interval cuts into it
+/// instead of materializing every post number in either list.
+fn subtract_intervals(include: &[(i32, i32)], exclude: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let mut result = Vec::new();
+    let mut ei = 0;
+
+    for &(lo, hi) in include {
+        let mut cur = lo;
+
+        while ei < exclude.len() && exclude[ei].1 < cur {
+            ei += 1;
+        }
+
+        let mut k = ei;
+        while cur <= hi && k < exclude.len() && exclude[k].0 <= hi {
+            let (e_lo, e_hi) = exclude[k];
+            if e_lo > cur {
+                result.push((cur, e_lo - 1));
+            }
+            cur = cur.max(e_hi + 1);
+
+            if e_hi <= hi {
+                k += 1;
+            } else {
+                break;
+            }
+        }
+
+        if cur <= hi {
+            result.push((cur, hi));
+        }
+        ei = k;
+    }
+
+    result
+}
+
 pub fn calculate_post_numbers(specs: Vec<RangeSpec>, max_post_number: i32) -> Vec<i32> {
+    calculate_post_numbers_with_extra(specs, max_post_number, &[])
+}
+
+/// Like [`calculate_post_numbers`], but seeds the included set with `extra_included` before
+/// applying the specs' own includes/excludes — lets a numeric query's `^`/`?^` exclusions also
+/// apply to post numbers resolved some other way, e.g. from a [`DateRangeSpec`] window.
+pub fn calculate_post_numbers_with_extra(
+    specs: Vec<RangeSpec>,
+    max_post_number: i32,
+    extra_included: &[i32],
+) -> Vec<i32> {
+    calculate_post_intervals_with_extra(specs, max_post_number, extra_included)
+        .into_iter()
+        .flat_map(|(lo, hi)| lo..=hi)
+        .collect()
+}
+
+/// Like [`calculate_post_numbers`], but returns the result as a sorted, non-overlapping list of
+/// `(lo, hi)` intervals instead of expanding every post number — for an open-ended spec like
+/// `1-` against a board with hundreds of thousands of posts, this is O(number of ranges) rather
+/// than O(total posts).
+pub fn calculate_post_intervals(specs: Vec<RangeSpec>, max_post_number: i32) -> Vec<(i32, i32)> {
+    calculate_post_intervals_with_extra(specs, max_post_number, &[])
+}
+
+/// Interval-arithmetic counterpart to [`calculate_post_numbers_with_extra`].
+pub fn calculate_post_intervals_with_extra(
+    specs: Vec<RangeSpec>,
+    max_post_number: i32,
+    extra_included: &[i32],
+) -> Vec<(i32, i32)> {
     debug!(
-        "calculate_post_numbers called with specs: {specs:?}, max_post_number: {max_post_number}"
+        "calculate_post_intervals called with specs: {specs:?}, max_post_number: {max_post_number}"
     );
-    let mut included = HashSet::new();
-    let mut excluded = HashSet::new();
+
+    let mut included: Vec<(i32, i32)> = extra_included.iter().map(|&n| (n, n)).collect();
+    let mut excluded: Vec<(i32, i32)> = Vec::new();
 
     // Helper function to calculate absolute post number from relative reference
     let calculate_absolute = |relative_num: i32, digit_count: usize| -> i32 {
@@ -181,88 +884,92 @@ pub fn calculate_post_numbers(specs: Vec<RangeSpec>, max_post_number: i32) -> Ve
         result
     };
 
+    // Stops one short of `end` for exclusive endpoints; inclusive ranges (the default, plain
+    // `-` syntax) still walk through `end` itself.
+    let last_in_range = |end: i32, inclusion: RangeInclusion| match inclusion {
+        RangeInclusion::Inclusive => end,
+        RangeInclusion::Exclusive => end - 1,
+    };
+
+    // A strided include (step > 1) isn't contiguous, so it can't collapse into one `(lo, hi)`
+    // pair; it contributes one singleton interval per selected post instead. Strides are used for
+    // sampling a bounded slice of a range, so this stays small in practice even though it's no
+    // longer O(1) in the stride count.
+    let strided_singletons = |start: i32, end: i32, step: Option<i32>| -> Vec<(i32, i32)> {
+        match step {
+            Some(step) if step > 1 => (start..=end)
+                .step_by(step as usize)
+                .map(|i| (i, i))
+                .collect(),
+            _ => vec![(start, end)],
+        }
+    };
+
     for spec in specs {
         match spec {
-            RangeSpec::Include(start, end) => {
+            RangeSpec::Include(start, end, inclusion, step) => {
                 if let Some(end_num) = end {
-                    for i in start..=end_num {
-                        included.insert(i);
-                    }
+                    included.extend(strided_singletons(
+                        start,
+                        last_in_range(end_num, inclusion),
+                        step,
+                    ));
                 } else {
-                    included.insert(start);
+                    included.push((start, start));
                 }
             }
-            RangeSpec::IncludeFrom(start) => {
-                // Include all posts from start to max_post_number
-                for i in start..=max_post_number {
-                    included.insert(i);
-                }
+            RangeSpec::IncludeFrom(start, step) => {
+                included.extend(strided_singletons(start, max_post_number, step));
             }
-            RangeSpec::Exclude(start, end) => {
+            RangeSpec::Exclude(start, end, inclusion) => {
                 if let Some(end_num) = end {
-                    for i in start..=end_num {
-                        excluded.insert(i);
-                    }
+                    excluded.push((start, last_in_range(end_num, inclusion)));
                 } else {
-                    excluded.insert(start);
+                    excluded.push((start, start));
                 }
             }
             RangeSpec::ExcludeFrom(start) => {
-                // Exclude all posts from start to max_post_number
-                for i in start..=max_post_number {
-                    excluded.insert(i);
-                }
+                excluded.push((start, max_post_number));
             }
             // Relative references
-            RangeSpec::RelativeInclude(start, end, digit_count) => {
-                debug!(
-                    "Processing RelativeInclude: start={start}, end={end:?}, digit_count={digit_count}"
-                );
+            RangeSpec::RelativeInclude(start, end, digit_count, inclusion, step) => {
                 let abs_start = calculate_absolute(start, digit_count);
                 if let Some(end_num) = end {
-                    let abs_end = calculate_absolute(end_num, digit_count);
+                    let abs_end =
+                        last_in_range(calculate_absolute(end_num, digit_count), inclusion);
                     debug!("Including range {abs_start}..={abs_end}");
-                    for i in abs_start..=abs_end {
-                        included.insert(i);
-                    }
+                    included.extend(strided_singletons(abs_start, abs_end, step));
                 } else {
                     debug!("Including single number {abs_start}");
-                    included.insert(abs_start);
+                    included.push((abs_start, abs_start));
                 }
             }
-            RangeSpec::RelativeIncludeFrom(start, digit_count) => {
+            RangeSpec::RelativeIncludeFrom(start, digit_count, step) => {
                 let abs_start = calculate_absolute(start, digit_count);
-                for i in abs_start..=max_post_number {
-                    included.insert(i);
-                }
+                included.extend(strided_singletons(abs_start, max_post_number, step));
             }
-            RangeSpec::RelativeExclude(start, end, digit_count) => {
-                debug!(
-                    "Processing RelativeExclude: start={start}, end={end:?}, digit_count={digit_count}"
-                );
+            RangeSpec::RelativeExclude(start, end, digit_count, inclusion) => {
                 let abs_start = calculate_absolute(start, digit_count);
                 if let Some(end_num) = end {
-                    let abs_end = calculate_absolute(end_num, digit_count);
+                    let abs_end =
+                        last_in_range(calculate_absolute(end_num, digit_count), inclusion);
                     debug!("Excluding range {abs_start}..={abs_end}");
-                    for i in abs_start..=abs_end {
-                        excluded.insert(i);
-                    }
+                    excluded.push((abs_start, abs_end));
                 } else {
                     debug!("Excluding single number {abs_start}");
-                    excluded.insert(abs_start);
+                    excluded.push((abs_start, abs_start));
                 }
             }
             RangeSpec::RelativeExcludeFrom(start, digit_count) => {
                 let abs_start = calculate_absolute(start, digit_count);
-                for i in abs_start..=max_post_number {
-                    excluded.insert(i);
-                }
+                excluded.push((abs_start, max_post_number));
             }
         }
     }
 
-    let mut result: Vec<i32> = included.difference(&excluded).cloned().collect();
-    result.sort();
+    let included = merge_intervals(included);
+    let excluded = merge_intervals(excluded);
+    let result = subtract_intervals(&included, &excluded);
     debug!("Final result - included: {included:?}, excluded: {excluded:?}, result: {result:?}");
     result
 }
@@ -274,31 +981,57 @@ mod tests {
     #[test]
     fn test_parse_single_number() {
         let specs = parse_range_specifications("123");
-        assert_eq!(specs, vec![RangeSpec::Include(123, None)]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::Include(
+                123,
+                None,
+                RangeInclusion::Inclusive,
+                None
+            )]
+        );
     }
 
     #[test]
     fn test_parse_range() {
         let specs = parse_range_specifications("123-128");
-        assert_eq!(specs, vec![RangeSpec::Include(123, Some(128))]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::Include(
+                123,
+                Some(128),
+                RangeInclusion::Inclusive,
+                None
+            )]
+        );
     }
 
     #[test]
     fn test_parse_open_range() {
         let specs = parse_range_specifications("123-");
-        assert_eq!(specs, vec![RangeSpec::IncludeFrom(123)]);
+        assert_eq!(specs, vec![RangeSpec::IncludeFrom(123, None)]);
     }
 
     #[test]
     fn test_parse_exclusion() {
         let specs = parse_range_specifications("^123");
-        assert_eq!(specs, vec![RangeSpec::Exclude(123, None)]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::Exclude(123, None, RangeInclusion::Inclusive)]
+        );
     }
 
     #[test]
     fn test_parse_exclusion_range() {
         let specs = parse_range_specifications("^123-128");
-        assert_eq!(specs, vec![RangeSpec::Exclude(123, Some(128))]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::Exclude(
+                123,
+                Some(128),
+                RangeInclusion::Inclusive
+            )]
+        );
     }
 
     #[test]
@@ -310,31 +1043,65 @@ mod tests {
     #[test]
     fn test_parse_relative_single() {
         let specs = parse_range_specifications("?324");
-        assert_eq!(specs, vec![RangeSpec::RelativeInclude(324, None, 3)]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::RelativeInclude(
+                324,
+                None,
+                3,
+                RangeInclusion::Inclusive,
+                None
+            )]
+        );
     }
 
     #[test]
     fn test_parse_relative_range() {
         let specs = parse_range_specifications("?324-326");
-        assert_eq!(specs, vec![RangeSpec::RelativeInclude(324, Some(326), 3)]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::RelativeInclude(
+                324,
+                Some(326),
+                3,
+                RangeInclusion::Inclusive,
+                None
+            )]
+        );
     }
 
     #[test]
     fn test_parse_relative_open_range() {
         let specs = parse_range_specifications("?300-");
-        assert_eq!(specs, vec![RangeSpec::RelativeIncludeFrom(300, 3)]);
+        assert_eq!(specs, vec![RangeSpec::RelativeIncludeFrom(300, 3, None)]);
     }
 
     #[test]
     fn test_parse_relative_exclusion() {
         let specs = parse_range_specifications("?^325");
-        assert_eq!(specs, vec![RangeSpec::RelativeExclude(325, None, 3)]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::RelativeExclude(
+                325,
+                None,
+                3,
+                RangeInclusion::Inclusive
+            )]
+        );
     }
 
     #[test]
     fn test_parse_relative_exclusion_range() {
         let specs = parse_range_specifications("?^325-327");
-        assert_eq!(specs, vec![RangeSpec::RelativeExclude(325, Some(327), 3)]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::RelativeExclude(
+                325,
+                Some(327),
+                3,
+                RangeInclusion::Inclusive
+            )]
+        );
     }
 
     #[test]
@@ -343,12 +1110,12 @@ mod tests {
         assert_eq!(
             specs,
             vec![
-                RangeSpec::Include(10, None),
-                RangeSpec::Include(20, Some(25)),
-                RangeSpec::Include(30, None),
-                RangeSpec::Exclude(23, None),
-                RangeSpec::RelativeInclude(324, None, 3),
-                RangeSpec::RelativeExclude(326, None, 3),
+                RangeSpec::Include(10, None, RangeInclusion::Inclusive, None),
+                RangeSpec::Include(20, Some(25), RangeInclusion::Inclusive, None),
+                RangeSpec::Include(30, None, RangeInclusion::Inclusive, None),
+                RangeSpec::Exclude(23, None, RangeInclusion::Inclusive),
+                RangeSpec::RelativeInclude(324, None, 3, RangeInclusion::Inclusive, None),
+                RangeSpec::RelativeExclude(326, None, 3, RangeInclusion::Inclusive),
             ]
         );
     }
@@ -358,27 +1125,132 @@ mod tests {
         let specs = parse_range_specifications("  123  ,  ,  456  ");
         assert_eq!(
             specs,
-            vec![RangeSpec::Include(123, None), RangeSpec::Include(456, None)]
+            vec![
+                RangeSpec::Include(123, None, RangeInclusion::Inclusive, None),
+                RangeSpec::Include(456, None, RangeInclusion::Inclusive, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_str_single_number() {
+        let spec: RangeSpec = "123".parse().unwrap();
+        assert_eq!(
+            spec,
+            RangeSpec::Include(123, None, RangeInclusion::Inclusive, None)
+        );
+    }
+
+    #[test]
+    fn test_from_str_range() {
+        let spec: RangeSpec = "123-128".parse().unwrap();
+        assert_eq!(
+            spec,
+            RangeSpec::Include(123, Some(128), RangeInclusion::Inclusive, None)
+        );
+    }
+
+    #[test]
+    fn test_from_str_relative_exclusion() {
+        let spec: RangeSpec = "?^325".parse().unwrap();
+        assert_eq!(
+            spec,
+            RangeSpec::RelativeExclude(325, None, 3, RangeInclusion::Inclusive)
+        );
+    }
+
+    #[test]
+    fn test_from_str_invalid_number() {
+        let err = "120-abc".parse::<RangeSpec>().unwrap_err();
+        assert_eq!(
+            err,
+            RangeParseError::InvalidNumber {
+                token: "abc".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_str_stray_exclusion_marker() {
+        let err = "?^".parse::<RangeSpec>().unwrap_err();
+        assert_eq!(err, RangeParseError::EmptyToken);
+    }
+
+    #[test]
+    fn test_from_str_reversed_range() {
+        let err = "128-123".parse::<RangeSpec>().unwrap_err();
+        assert_eq!(
+            err,
+            RangeParseError::ReversedRange {
+                start: 128,
+                end: 123
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_str_negative_result() {
+        // The dash is also the inclusive range separator, so with that separator a negative
+        // bound can only show up as the end of a range, e.g. "5--2" (start=5, end=-2).
+        let err = "5--2".parse::<RangeSpec>().unwrap_err();
+        assert_eq!(err, RangeParseError::NegativeResult);
+    }
+
+    #[test]
+    fn test_parse_checked_collects_all_errors() {
+        let result = parse_range_specifications_checked("10,20-25,^bad,?324,?^");
+        let errors = result.unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                RangeParseError::InvalidNumber {
+                    token: "bad".to_string()
+                },
+                RangeParseError::EmptyToken,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_checked_valid_input() {
+        let specs = parse_range_specifications_checked("10,20-25,^23").unwrap();
+        assert_eq!(
+            specs,
+            vec![
+                RangeSpec::Include(10, None, RangeInclusion::Inclusive, None),
+                RangeSpec::Include(20, Some(25), RangeInclusion::Inclusive, None),
+                RangeSpec::Exclude(23, None, RangeInclusion::Inclusive),
+            ]
         );
     }
 
     #[test]
     fn test_calculate_single_number() {
-        let specs = vec![RangeSpec::Include(123, None)];
+        let specs = vec![RangeSpec::Include(
+            123,
+            None,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, 1000);
         assert_eq!(result, vec![123]);
     }
 
     #[test]
     fn test_calculate_range() {
-        let specs = vec![RangeSpec::Include(123, Some(126))];
+        let specs = vec![RangeSpec::Include(
+            123,
+            Some(126),
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, 1000);
         assert_eq!(result, vec![123, 124, 125, 126]);
     }
 
     #[test]
     fn test_calculate_open_range() {
-        let specs = vec![RangeSpec::IncludeFrom(998)];
+        let specs = vec![RangeSpec::IncludeFrom(998, None)];
         let result = calculate_post_numbers(specs, 1000);
         assert_eq!(result, vec![998, 999, 1000]);
     }
@@ -386,8 +1258,8 @@ mod tests {
     #[test]
     fn test_calculate_with_exclusion() {
         let specs = vec![
-            RangeSpec::Include(123, Some(128)),
-            RangeSpec::Exclude(126, None),
+            RangeSpec::Include(123, Some(128), RangeInclusion::Inclusive, None),
+            RangeSpec::Exclude(126, None, RangeInclusion::Inclusive),
         ];
         let result = calculate_post_numbers(specs, 1000);
         assert_eq!(result, vec![123, 124, 125, 127, 128]);
@@ -396,8 +1268,8 @@ mod tests {
     #[test]
     fn test_calculate_with_exclusion_range() {
         let specs = vec![
-            RangeSpec::Include(100, Some(110)),
-            RangeSpec::Exclude(105, Some(107)),
+            RangeSpec::Include(100, Some(110), RangeInclusion::Inclusive, None),
+            RangeSpec::Exclude(105, Some(107), RangeInclusion::Inclusive),
         ];
         let result = calculate_post_numbers(specs, 1000);
         assert_eq!(result, vec![100, 101, 102, 103, 104, 108, 109, 110]);
@@ -405,14 +1277,26 @@ mod tests {
 
     #[test]
     fn test_calculate_relative_reference() {
-        let specs = vec![RangeSpec::RelativeInclude(324, None, 3)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            324,
+            None,
+            3,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, 123340);
         assert_eq!(result, vec![123324]);
     }
 
     #[test]
     fn test_calculate_relative_range() {
-        let specs = vec![RangeSpec::RelativeInclude(324, Some(326), 3)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            324,
+            Some(326),
+            3,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, 123340);
         assert_eq!(result, vec![123324, 123325, 123326]);
     }
@@ -420,8 +1304,8 @@ mod tests {
     #[test]
     fn test_calculate_relative_with_exclusion() {
         let specs = vec![
-            RangeSpec::RelativeInclude(320, Some(330), 3),
-            RangeSpec::RelativeExclude(325, None, 3),
+            RangeSpec::RelativeInclude(320, Some(330), 3, RangeInclusion::Inclusive, None),
+            RangeSpec::RelativeExclude(325, None, 3, RangeInclusion::Inclusive),
         ];
         let result = calculate_post_numbers(specs, 123340);
         let expected: Vec<i32> = (123320..=123330).filter(|&x| x != 123325).collect();
@@ -430,7 +1314,7 @@ mod tests {
 
     #[test]
     fn test_calculate_relative_open_range() {
-        let specs = vec![RangeSpec::RelativeIncludeFrom(338, 3)];
+        let specs = vec![RangeSpec::RelativeIncludeFrom(338, 3, None)];
         let result = calculate_post_numbers(specs, 123340);
         assert_eq!(result, vec![123338, 123339, 123340]);
     }
@@ -438,10 +1322,10 @@ mod tests {
     #[test]
     fn test_calculate_complex_mix() {
         let specs = vec![
-            RangeSpec::Include(100, Some(105)),
-            RangeSpec::RelativeInclude(324, None, 3),
-            RangeSpec::Exclude(102, None),
-            RangeSpec::RelativeExclude(324, None, 3),
+            RangeSpec::Include(100, Some(105), RangeInclusion::Inclusive, None),
+            RangeSpec::RelativeInclude(324, None, 3, RangeInclusion::Inclusive, None),
+            RangeSpec::Exclude(102, None, RangeInclusion::Inclusive),
+            RangeSpec::RelativeExclude(324, None, 3, RangeInclusion::Inclusive),
         ];
         let result = calculate_post_numbers(specs, 123340);
         assert_eq!(result, vec![100, 101, 103, 104, 105]);
@@ -450,7 +1334,13 @@ mod tests {
     #[test]
     fn test_calculate_with_small_max_number() {
         // Test when max_post_number is less than 1000
-        let specs = vec![RangeSpec::RelativeInclude(324, None, 3)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            324,
+            None,
+            3,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, 500);
         assert_eq!(result, vec![324]); // Base is 0, so relative becomes absolute
     }
@@ -458,7 +1348,12 @@ mod tests {
     #[test]
     fn test_calculate_edge_cases() {
         // Test with max_post_number of 0
-        let specs = vec![RangeSpec::Include(1, Some(3))];
+        let specs = vec![RangeSpec::Include(
+            1,
+            Some(3),
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, 0);
         assert_eq!(result, vec![1, 2, 3]);
 
@@ -471,9 +1366,9 @@ mod tests {
     #[test]
     fn test_calculate_overlapping_ranges() {
         let specs = vec![
-            RangeSpec::Include(1, Some(10)),
-            RangeSpec::Include(5, Some(15)),
-            RangeSpec::Exclude(8, Some(12)),
+            RangeSpec::Include(1, Some(10), RangeInclusion::Inclusive, None),
+            RangeSpec::Include(5, Some(15), RangeInclusion::Inclusive, None),
+            RangeSpec::Exclude(8, Some(12), RangeInclusion::Inclusive),
         ];
         let result = calculate_post_numbers(specs, 100);
         let expected: Vec<i32> = vec![1, 2, 3, 4, 5, 6, 7, 13, 14, 15];
@@ -484,20 +1379,38 @@ mod tests {
     fn test_parse_relative_with_different_digits() {
         // Test 2 digits
         let specs = parse_range_specifications("?24");
-        assert_eq!(specs, vec![RangeSpec::RelativeInclude(24, None, 2)]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::RelativeInclude(
+                24,
+                None,
+                2,
+                RangeInclusion::Inclusive,
+                None
+            )]
+        );
 
         // Test 4 digits
         let specs = parse_range_specifications("?1234");
-        assert_eq!(specs, vec![RangeSpec::RelativeInclude(1234, None, 4)]);
+        assert_eq!(
+            specs,
+            vec![RangeSpec::RelativeInclude(
+                1234,
+                None,
+                4,
+                RangeInclusion::Inclusive,
+                None
+            )]
+        );
 
         // Test mixed digits
         let specs = parse_range_specifications("?24,?324,?1234");
         assert_eq!(
             specs,
             vec![
-                RangeSpec::RelativeInclude(24, None, 2),
-                RangeSpec::RelativeInclude(324, None, 3),
-                RangeSpec::RelativeInclude(1234, None, 4),
+                RangeSpec::RelativeInclude(24, None, 2, RangeInclusion::Inclusive, None),
+                RangeSpec::RelativeInclude(324, None, 3, RangeInclusion::Inclusive, None),
+                RangeSpec::RelativeInclude(1234, None, 4, RangeInclusion::Inclusive, None),
             ]
         );
     }
@@ -508,23 +1421,47 @@ mod tests {
         let max_post = 123456;
 
         // ?56 with 2 digits: 123456 / 100 * 100 + 56 = 123456
-        let specs = vec![RangeSpec::RelativeInclude(56, None, 2)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            56,
+            None,
+            2,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, max_post);
         assert_eq!(result, vec![123456]);
 
         // ?456 with 3 digits: 123456 / 1000 * 1000 + 456 = 123456
-        let specs = vec![RangeSpec::RelativeInclude(456, None, 3)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            456,
+            None,
+            3,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, max_post);
         assert_eq!(result, vec![123456]);
 
         // ?3456 with 4 digits: 123456 / 10000 * 10000 + 3456 = 123456
-        let specs = vec![RangeSpec::RelativeInclude(3456, None, 4)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            3456,
+            None,
+            4,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, max_post);
         assert_eq!(result, vec![123456]);
 
         // Test with different values
         // ?24 with 2 digits: 123456 / 100 * 100 + 24 = 123424
-        let specs = vec![RangeSpec::RelativeInclude(24, None, 2)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            24,
+            None,
+            2,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, max_post);
         assert_eq!(result, vec![123424]);
     }
@@ -535,17 +1472,35 @@ mod tests {
         let max_post = 2345;
 
         // ?456 with max 2345: since 2456 > 2345, should wrap to 1456
-        let specs = vec![RangeSpec::RelativeInclude(456, None, 3)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            456,
+            None,
+            3,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, max_post);
         assert_eq!(result, vec![1456]);
 
         // ?345 with max 2345: should return 2345 (exact match)
-        let specs = vec![RangeSpec::RelativeInclude(345, None, 3)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            345,
+            None,
+            3,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, max_post);
         assert_eq!(result, vec![2345]);
 
         // ?100 with max 2345: should return 2100
-        let specs = vec![RangeSpec::RelativeInclude(100, None, 3)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            100,
+            None,
+            3,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, max_post);
         assert_eq!(result, vec![2100]);
 
@@ -553,13 +1508,345 @@ mod tests {
         let max_post = 456;
 
         // ?456 with max 456: should return 456 (exact match)
-        let specs = vec![RangeSpec::RelativeInclude(456, None, 3)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            456,
+            None,
+            3,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, max_post);
         assert_eq!(result, vec![456]);
 
         // ?500 with max 456: since we can't go negative, should return 500
-        let specs = vec![RangeSpec::RelativeInclude(500, None, 3)];
+        let specs = vec![RangeSpec::RelativeInclude(
+            500,
+            None,
+            3,
+            RangeInclusion::Inclusive,
+            None,
+        )];
         let result = calculate_post_numbers(specs, max_post);
         assert_eq!(result, vec![500]);
     }
+
+    #[test]
+    fn test_parse_exclusive_range_dotdot() {
+        let specs = parse_range_specifications("123..128");
+        assert_eq!(
+            specs,
+            vec![RangeSpec::Include(
+                123,
+                Some(128),
+                RangeInclusion::Exclusive,
+                None
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_exclusive_range_dash_lt() {
+        let specs = parse_range_specifications("123-<128");
+        assert_eq!(
+            specs,
+            vec![RangeSpec::Include(
+                123,
+                Some(128),
+                RangeInclusion::Exclusive,
+                None
+            )]
+        );
+    }
+
+    #[test]
+    fn test_from_str_exclusive_range() {
+        let spec: RangeSpec = "123..128".parse().unwrap();
+        assert_eq!(
+            spec,
+            RangeSpec::Include(123, Some(128), RangeInclusion::Exclusive, None)
+        );
+    }
+
+    #[test]
+    fn test_from_str_exclusive_exclusion_range() {
+        let spec: RangeSpec = "^10-<15".parse().unwrap();
+        assert_eq!(
+            spec,
+            RangeSpec::Exclude(10, Some(15), RangeInclusion::Exclusive)
+        );
+    }
+
+    #[test]
+    fn test_calculate_exclusive_range_stops_short() {
+        let specs = vec![RangeSpec::Include(
+            123,
+            Some(128),
+            RangeInclusion::Exclusive,
+            None,
+        )];
+        let result = calculate_post_numbers(specs, 1000);
+        assert_eq!(result, vec![123, 124, 125, 126, 127]);
+    }
+
+    #[test]
+    fn test_calculate_exclusive_exclusion_range() {
+        let specs = vec![
+            RangeSpec::Include(100, Some(110), RangeInclusion::Inclusive, None),
+            RangeSpec::Exclude(105, Some(108), RangeInclusion::Exclusive),
+        ];
+        let result = calculate_post_numbers(specs, 1000);
+        assert_eq!(result, vec![100, 101, 102, 103, 104, 108, 109, 110]);
+    }
+
+    #[test]
+    fn test_calculate_relative_exclusive_range() {
+        let specs = vec![RangeSpec::RelativeInclude(
+            324,
+            Some(327),
+            3,
+            RangeInclusion::Exclusive,
+            None,
+        )];
+        let result = calculate_post_numbers(specs, 123340);
+        assert_eq!(result, vec![123324, 123325, 123326]);
+    }
+
+    fn ndt(y: i32, m: u32, d: u32, h: u32, mi: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, mi, s)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_date_range_parse_single_day() {
+        let spec: DateRangeSpec = "2024-01-01".parse().unwrap();
+        assert_eq!(
+            spec,
+            DateRangeSpec::Absolute(ndt(2024, 1, 1, 0, 0, 0), ndt(2024, 1, 1, 23, 59, 59))
+        );
+    }
+
+    #[test]
+    fn test_date_range_parse_absolute_range() {
+        let spec: DateRangeSpec = "2024-01-01..2024-02-01".parse().unwrap();
+        assert_eq!(
+            spec,
+            DateRangeSpec::Absolute(ndt(2024, 1, 1, 0, 0, 0), ndt(2024, 2, 1, 23, 59, 59))
+        );
+    }
+
+    #[test]
+    fn test_date_range_parse_datetime_is_since() {
+        let spec: DateRangeSpec = "2024-01-01T10:30".parse().unwrap();
+        assert_eq!(spec, DateRangeSpec::Since(ndt(2024, 1, 1, 10, 30, 0)));
+    }
+
+    #[test]
+    fn test_date_range_parse_open_ended_since() {
+        let spec: DateRangeSpec = "2024-01-01-".parse().unwrap();
+        assert_eq!(spec, DateRangeSpec::Since(ndt(2024, 1, 1, 0, 0, 0)));
+    }
+
+    #[test]
+    fn test_date_range_parse_relative_days() {
+        let spec: DateRangeSpec = "-7d".parse().unwrap();
+        assert_eq!(spec, DateRangeSpec::RelativePast(Duration::days(7)));
+    }
+
+    #[test]
+    fn test_date_range_parse_relative_hours() {
+        let spec: DateRangeSpec = "-24h".parse().unwrap();
+        assert_eq!(spec, DateRangeSpec::RelativePast(Duration::hours(24)));
+    }
+
+    #[test]
+    fn test_date_range_parse_invalid_date() {
+        let err = "not-a-date".parse::<DateRangeSpec>().unwrap_err();
+        assert_eq!(
+            err,
+            DateRangeParseError::InvalidDate {
+                token: "not-a-date".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_date_range_parse_invalid_relative_offset() {
+        let err = "-7x".parse::<DateRangeSpec>().unwrap_err();
+        assert_eq!(
+            err,
+            DateRangeParseError::InvalidRelativeOffset {
+                token: "-7x".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_date_range_parse_reversed_range() {
+        let err = "2024-02-01..2024-01-01"
+            .parse::<DateRangeSpec>()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            DateRangeParseError::ReversedRange {
+                start: ndt(2024, 2, 1, 0, 0, 0),
+                end: ndt(2024, 1, 1, 23, 59, 59),
+            }
+        );
+    }
+
+    #[test]
+    fn test_date_range_parse_empty() {
+        let err = "  ".parse::<DateRangeSpec>().unwrap_err();
+        assert_eq!(err, DateRangeParseError::EmptyToken);
+    }
+
+    #[test]
+    fn test_date_range_resolve_absolute_is_unchanged() {
+        let spec = DateRangeSpec::Absolute(ndt(2024, 1, 1, 0, 0, 0), ndt(2024, 1, 1, 23, 59, 59));
+        assert_eq!(
+            spec.resolve(),
+            (ndt(2024, 1, 1, 0, 0, 0), ndt(2024, 1, 1, 23, 59, 59))
+        );
+    }
+
+    #[test]
+    fn test_date_range_resolve_relative_past_spans_duration() {
+        let spec = DateRangeSpec::RelativePast(Duration::hours(24));
+        let (start, end) = spec.resolve();
+        assert_eq!(end - start, Duration::hours(24));
+    }
+
+    #[test]
+    fn test_calculate_post_numbers_with_extra_combines_and_excludes() {
+        let specs = vec![RangeSpec::Exclude(105, None, RangeInclusion::Inclusive)];
+        let result = calculate_post_numbers_with_extra(specs, 1000, &[100, 105, 110]);
+        assert_eq!(result, vec![100, 110]);
+    }
+
+    #[test]
+    fn test_parse_range_with_step() {
+        let specs = parse_range_specifications("100-200:10");
+        assert_eq!(
+            specs,
+            vec![RangeSpec::Include(
+                100,
+                Some(200),
+                RangeInclusion::Inclusive,
+                Some(10)
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_relative_range_with_step() {
+        let specs = parse_range_specifications("?100-300:25");
+        assert_eq!(
+            specs,
+            vec![RangeSpec::RelativeInclude(
+                100,
+                Some(300),
+                3,
+                RangeInclusion::Inclusive,
+                Some(25)
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_open_range_with_step() {
+        let specs = parse_range_specifications("100-:10");
+        assert_eq!(specs, vec![RangeSpec::IncludeFrom(100, Some(10))]);
+    }
+
+    #[test]
+    fn test_from_str_range_with_step() {
+        let spec: RangeSpec = "100-200:10".parse().unwrap();
+        assert_eq!(
+            spec,
+            RangeSpec::Include(100, Some(200), RangeInclusion::Inclusive, Some(10))
+        );
+    }
+
+    #[test]
+    fn test_from_str_zero_step_is_error() {
+        let err = "100-200:0".parse::<RangeSpec>().unwrap_err();
+        assert_eq!(
+            err,
+            RangeParseError::InvalidStep {
+                token: "0".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_str_negative_step_is_error() {
+        let err = "100-200:-5".parse::<RangeSpec>().unwrap_err();
+        assert_eq!(
+            err,
+            RangeParseError::InvalidStep {
+                token: "-5".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_drops_token_with_invalid_step() {
+        let specs = parse_range_specifications("100-200:0,300");
+        assert_eq!(
+            specs,
+            vec![RangeSpec::Include(
+                300,
+                None,
+                RangeInclusion::Inclusive,
+                None
+            )]
+        );
+    }
+
+    #[test]
+    fn test_calculate_range_with_step() {
+        let specs = vec![RangeSpec::Include(
+            100,
+            Some(200),
+            RangeInclusion::Inclusive,
+            Some(10),
+        )];
+        let result = calculate_post_numbers(specs, 1000);
+        assert_eq!(
+            result,
+            vec![100, 110, 120, 130, 140, 150, 160, 170, 180, 190, 200]
+        );
+    }
+
+    #[test]
+    fn test_calculate_open_range_with_step() {
+        let specs = vec![RangeSpec::IncludeFrom(990, Some(5))];
+        let result = calculate_post_numbers(specs, 1000);
+        assert_eq!(result, vec![990, 995, 1000]);
+    }
+
+    #[test]
+    fn test_calculate_relative_range_with_step() {
+        let specs = vec![RangeSpec::RelativeInclude(
+            100,
+            Some(300),
+            3,
+            RangeInclusion::Inclusive,
+            Some(25),
+        )];
+        let result = calculate_post_numbers(specs, 123340);
+        assert_eq!(
+            result,
+            vec![123100, 123125, 123150, 123175, 123200, 123225, 123250, 123275, 123300]
+        );
+    }
+
+    #[test]
+    fn test_calculate_relative_open_range_with_step() {
+        let specs = vec![RangeSpec::RelativeIncludeFrom(330, 3, Some(5))];
+        let result = calculate_post_numbers(specs, 123340);
+        assert_eq!(result, vec![123330, 123335, 123340]);
+    }
 }
@@ -1,24 +1,559 @@
+mod pagination;
+mod phash;
+mod render;
+mod subscriptions;
+
+use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use bbs_fetch_post_discord_bot::{
-    calculate_post_numbers, get_max_post_number, get_res_by_numbers, parse_range_specifications,
-    RangeSpec,
+    add_subscription, calculate_post_numbers_with_extra, get_all_oekaki_hashes,
+    get_max_post_number, get_oekaki_hash, get_res_by_date_range, get_res_by_numbers,
+    parse_range_specifications_checked, remove_subscription, search_res_by_text, store_oekaki_hash,
+    DateRangeSpec, RangeSpec,
 };
 use log::{debug, error, info};
 use regex::Regex;
 use serenity::async_trait;
-use serenity::builder::{CreateEmbed, CreateMessage};
+use serenity::builder::{
+    CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponse,
+    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, CreateMessage,
+    EditInteractionResponse,
+};
+use serenity::model::application::ResolvedValue;
+use serenity::model::application::{
+    Command, CommandInteraction, CommandOptionType, ComponentInteraction, Interaction,
+};
 use serenity::model::channel::Message;
 use serenity::model::gateway::Ready;
+use serenity::model::id::MessageId;
 use serenity::prelude::*;
 use sqlx::postgres::PgPool;
+use tokio::sync::Mutex;
+
+use pagination::PaginationState;
+
+/// How long a paginated message's state is kept around for button clicks before it's swept, so
+/// `Bot::pagination` doesn't grow without bound on a long-running bot.
+const PAGINATION_TTL: Duration = Duration::from_secs(3600);
 
 #[derive(Clone)]
 struct Bot {
     pool: Arc<PgPool>,
     image_url_prefix: String,
+    pagination: Arc<Mutex<HashMap<MessageId, (Instant, PaginationState)>>>,
+}
+
+impl Bot {
+    /// Drops pagination entries older than [`PAGINATION_TTL`]; called before every insert so the
+    /// map stays bounded by recent activity rather than growing forever.
+    async fn evict_stale_pagination(&self) {
+        let mut pagination = self.pagination.lock().await;
+        pagination.retain(|_, (created_at, _)| created_at.elapsed() < PAGINATION_TTL);
+    }
+
+    async fn respond_ephemeral(&self, ctx: &Context, command: &CommandInteraction, content: &str) {
+        let builder = CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(content)
+                .ephemeral(true),
+        );
+        if let Err(e) = command.create_response(&ctx.http, builder).await {
+            error!("Error sending interaction response: {e:?}");
+        }
+    }
+
+    async fn handle_fetch_command(&self, ctx: &Context, command: &CommandInteraction) {
+        let options = command.data.options();
+
+        let range = options.iter().find_map(|opt| match (opt.name, &opt.value) {
+            ("range", ResolvedValue::String(s)) => Some(s.to_string()),
+            _ => None,
+        });
+
+        let date = options.iter().find_map(|opt| match (opt.name, &opt.value) {
+            ("date", ResolvedValue::String(s)) => Some(s.to_string()),
+            _ => None,
+        });
+
+        let embed = options
+            .iter()
+            .find_map(|opt| match (opt.name, &opt.value) {
+                ("embed", ResolvedValue::Boolean(b)) => Some(*b),
+                _ => None,
+            })
+            .unwrap_or(false);
+
+        if range.is_none() && date.is_none() {
+            self.respond_ephemeral(ctx, command, "range または date オプションが必要です。")
+                .await;
+            return;
+        }
+
+        let specs = match &range {
+            Some(range) => match parse_range_specifications_checked(range) {
+                Ok(specs) if specs.is_empty() => {
+                    self.respond_ephemeral(
+                        ctx,
+                        command,
+                        "範囲の指定を解釈できませんでした。例: 123-128 または ^322 または ?324-326",
+                    )
+                    .await;
+                    return;
+                }
+                Ok(specs) => specs,
+                Err(errors) => {
+                    let detail = errors
+                        .iter()
+                        .map(|e| e.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    self.respond_ephemeral(
+                        ctx,
+                        command,
+                        &format!("範囲の指定を解釈できませんでした: {detail}"),
+                    )
+                    .await;
+                    return;
+                }
+            },
+            None => Vec::new(),
+        };
+
+        let date_spec = match &date {
+            Some(date) => match date.parse::<DateRangeSpec>() {
+                Ok(spec) => Some(spec),
+                Err(e) => {
+                    self.respond_ephemeral(
+                        ctx,
+                        command,
+                        &format!("日時の指定を解釈できませんでした: {e}"),
+                    )
+                    .await;
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        debug!("/fetch range={range:?}, date={date:?}, embed={embed}, parsed specs: {specs:?}");
+
+        if let Err(e) = command.defer(&ctx.http).await {
+            error!("Error deferring /fetch interaction: {e:?}");
+            return;
+        }
+
+        let needs_max = specs.iter().any(|spec| {
+            matches!(
+                spec,
+                RangeSpec::IncludeFrom(_, _)
+                    | RangeSpec::ExcludeFrom(_)
+                    | RangeSpec::RelativeInclude(_, _, _, _, _)
+                    | RangeSpec::RelativeExclude(_, _, _, _)
+                    | RangeSpec::RelativeIncludeFrom(_, _, _)
+                    | RangeSpec::RelativeExcludeFrom(_, _)
+            )
+        });
+
+        let max_post_number = if needs_max {
+            match get_max_post_number(&self.pool).await {
+                Ok(max) => max,
+                Err(e) => {
+                    error!("Error getting max post number: {e:?}");
+                    self.edit_response(ctx, command, "データベースエラーが発生しました。")
+                        .await;
+                    return;
+                }
+            }
+        } else {
+            0
+        };
+
+        let date_numbers = if let Some(date_spec) = date_spec {
+            let (start, end) = date_spec.resolve();
+            match get_res_by_date_range(&self.pool, start, end).await {
+                Ok(posts) => posts.into_iter().map(|post| post.no).collect(),
+                Err(e) => {
+                    error!("Error fetching posts by date range: {e:?}");
+                    self.edit_response(ctx, command, "データベースエラーが発生しました。")
+                        .await;
+                    return;
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        let post_numbers = calculate_post_numbers_with_extra(specs, max_post_number, &date_numbers);
+        if post_numbers.is_empty() {
+            self.edit_response(ctx, command, "指定された範囲には表示するレスがありません。")
+                .await;
+            return;
+        }
+
+        match get_res_by_numbers(&self.pool, post_numbers.clone()).await {
+            Ok(posts) if posts.is_empty() => {
+                self.edit_response(ctx, command, "指定された範囲のレスが見つかりませんでした。")
+                    .await;
+            }
+            Ok(posts) => {
+                self.send_fetch_results(ctx, command, &posts, embed).await;
+            }
+            Err(e) => {
+                error!("Database error in /fetch: {e:?}");
+                self.edit_response(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+            }
+        }
+    }
+
+    async fn handle_subscribe_command(&self, ctx: &Context, command: &CommandInteraction) {
+        let channel_id = command.channel_id.get() as i64;
+
+        let max = match get_max_post_number(&self.pool).await {
+            Ok(max) => max,
+            Err(e) => {
+                error!("Error getting max post number for /subscribe: {e:?}");
+                self.respond_ephemeral(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+                return;
+            }
+        };
+
+        match add_subscription(&self.pool, channel_id, max).await {
+            Ok(()) => {
+                self.respond_ephemeral(
+                    ctx,
+                    command,
+                    "このチャンネルを新着レスの通知登録に追加しました。",
+                )
+                .await
+            }
+            Err(e) => {
+                error!("Error adding subscription: {e:?}");
+                self.respond_ephemeral(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+            }
+        }
+    }
+
+    async fn handle_unsubscribe_command(&self, ctx: &Context, command: &CommandInteraction) {
+        let channel_id = command.channel_id.get() as i64;
+
+        match remove_subscription(&self.pool, channel_id).await {
+            Ok(()) => {
+                self.respond_ephemeral(ctx, command, "このチャンネルの通知登録を解除しました。")
+                    .await
+            }
+            Err(e) => {
+                error!("Error removing subscription: {e:?}");
+                self.respond_ephemeral(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+            }
+        }
+    }
+
+    async fn handle_similar_command(&self, ctx: &Context, command: &CommandInteraction) {
+        const DISTANCE_THRESHOLD: u32 = 10;
+
+        let post_no = command
+            .data
+            .options()
+            .iter()
+            .find_map(|opt| match opt.value {
+                ResolvedValue::Integer(n) => Some(n as i32),
+                _ => None,
+            });
+
+        let Some(post_no) = post_no else {
+            self.respond_ephemeral(ctx, command, "post_number オプションが必要です。")
+                .await;
+            return;
+        };
+
+        if let Err(e) = command.defer(&ctx.http).await {
+            error!("Error deferring /similar interaction: {e:?}");
+            return;
+        }
+
+        let target = match get_res_by_numbers(&self.pool, vec![post_no]).await {
+            Ok(mut posts) => posts.pop(),
+            Err(e) => {
+                error!("Error fetching post for /similar: {e:?}");
+                self.edit_response(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+                return;
+            }
+        };
+
+        let Some(target) = target else {
+            self.edit_response(ctx, command, "指定されたレスが見つかりませんでした。")
+                .await;
+            return;
+        };
+
+        let Some(oekaki_id) = target.oekaki_id else {
+            self.edit_response(ctx, command, "このレスにはお絵かきが含まれていません。")
+                .await;
+            return;
+        };
+
+        let target_hash = match get_oekaki_hash(&self.pool, post_no).await {
+            Ok(Some(hash)) => hash,
+            Ok(None) => {
+                let image_url = format!("{}{}.png", self.image_url_prefix, oekaki_id);
+                match phash::fetch_and_hash(&image_url).await {
+                    Ok(hash) => {
+                        let hash = hash as i64;
+                        if let Err(e) = store_oekaki_hash(&self.pool, post_no, hash).await {
+                            error!("Error storing oekaki hash: {e:?}");
+                        }
+                        hash
+                    }
+                    Err(e) => {
+                        error!("Error hashing oekaki image for /similar: {e:?}");
+                        self.edit_response(ctx, command, "画像の解析に失敗しました。")
+                            .await;
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Error reading cached oekaki hash: {e:?}");
+                self.edit_response(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+                return;
+            }
+        };
+
+        let all_hashes = match get_all_oekaki_hashes(&self.pool).await {
+            Ok(hashes) => hashes,
+            Err(e) => {
+                error!("Error loading oekaki hashes for /similar: {e:?}");
+                self.edit_response(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+                return;
+            }
+        };
+
+        let mut matches: Vec<(i32, u32)> = all_hashes
+            .into_iter()
+            .filter(|h| h.post_no != post_no)
+            .map(|h| {
+                (
+                    h.post_no,
+                    phash::hamming_distance(target_hash as u64, h.hash as u64),
+                )
+            })
+            .filter(|(_, distance)| *distance <= DISTANCE_THRESHOLD)
+            .collect();
+        matches.sort_by_key(|(_, distance)| *distance);
+
+        if matches.is_empty() {
+            self.edit_response(ctx, command, "類似するお絵かきは見つかりませんでした。")
+                .await;
+            return;
+        }
+
+        let numbers: Vec<i32> = matches.into_iter().map(|(no, _)| no).collect();
+        match get_res_by_numbers(&self.pool, numbers).await {
+            Ok(posts) => self.send_fetch_results(ctx, command, &posts, true).await,
+            Err(e) => {
+                error!("Error fetching similar posts: {e:?}");
+                self.edit_response(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+            }
+        }
+    }
+
+    async fn handle_search_command(&self, ctx: &Context, command: &CommandInteraction) {
+        const SEARCH_LIMIT: i64 = 20;
+
+        let keyword = command
+            .data
+            .options()
+            .iter()
+            .find_map(|opt| match &opt.value {
+                ResolvedValue::String(s) => Some(s.to_string()),
+                _ => None,
+            });
+
+        let Some(keyword) = keyword else {
+            self.respond_ephemeral(ctx, command, "keyword オプションが必要です。")
+                .await;
+            return;
+        };
+
+        if let Err(e) = command.defer(&ctx.http).await {
+            error!("Error deferring /search interaction: {e:?}");
+            return;
+        }
+
+        let post_numbers = match search_res_by_text(&self.pool, &keyword, SEARCH_LIMIT).await {
+            Ok(numbers) => numbers,
+            Err(e) => {
+                error!("Error searching posts: {e:?}");
+                self.edit_response(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+                return;
+            }
+        };
+
+        if post_numbers.is_empty() {
+            self.edit_response(ctx, command, "該当するレスが見つかりませんでした。")
+                .await;
+            return;
+        }
+
+        match get_res_by_numbers(&self.pool, post_numbers).await {
+            Ok(posts) => self.send_fetch_results(ctx, command, &posts, false).await,
+            Err(e) => {
+                error!("Database error in /search: {e:?}");
+                self.edit_response(ctx, command, "データベースエラーが発生しました。")
+                    .await;
+            }
+        }
+    }
+
+    async fn edit_response(&self, ctx: &Context, command: &CommandInteraction, content: &str) {
+        let builder = EditInteractionResponse::new().content(content);
+        if let Err(e) = command.edit_response(&ctx.http, builder).await {
+            error!("Error editing interaction response: {e:?}");
+        }
+    }
+
+    async fn send_fetch_results(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        posts: &[bbs_fetch_post_discord_bot::Res],
+        embed: bool,
+    ) {
+        let mut first = true;
+
+        if embed {
+            for post in posts {
+                let post_embed = render::render_post_embed(post, &self.image_url_prefix);
+                self.send_embed(ctx, command, &mut first, post_embed).await;
+            }
+        } else {
+            let mut current_message = String::new();
+
+            for post in posts {
+                let post_text = format!("{post}");
+
+                if !current_message.is_empty() && current_message.len() + post_text.len() > 1800 {
+                    let content = std::mem::take(&mut current_message);
+                    self.send_content(ctx, command, &mut first, content).await;
+                }
+
+                current_message.push_str(&post_text);
+
+                if let Some(oekaki_id) = post.oekaki_id {
+                    if !current_message.is_empty() {
+                        let content = std::mem::take(&mut current_message);
+                        self.send_content(ctx, command, &mut first, content).await;
+                    }
+
+                    let image_url = format!("{}{}.png", self.image_url_prefix, oekaki_id);
+                    self.send_embed(
+                        ctx,
+                        command,
+                        &mut first,
+                        CreateEmbed::new().image(image_url),
+                    )
+                    .await;
+                }
+            }
+
+            if !current_message.is_empty() {
+                self.send_content(ctx, command, &mut first, current_message)
+                    .await;
+            }
+        }
+    }
+
+    /// Sends the first chunk by editing the deferred response, then falls back to follow-up
+    /// messages for everything after.
+    async fn send_content(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        first: &mut bool,
+        content: String,
+    ) {
+        if std::mem::take(first) {
+            let builder = EditInteractionResponse::new().content(content);
+            if let Err(e) = command.edit_response(&ctx.http, builder).await {
+                error!("Error editing interaction response: {e:?}");
+            }
+        } else {
+            let builder = CreateInteractionResponseFollowup::new().content(content);
+            if let Err(e) = command.create_followup(&ctx.http, builder).await {
+                error!("Error sending follow-up message: {e:?}");
+            }
+        }
+    }
+
+    async fn send_embed(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        first: &mut bool,
+        embed: CreateEmbed,
+    ) {
+        if std::mem::take(first) {
+            let builder = EditInteractionResponse::new().embed(embed);
+            if let Err(e) = command.edit_response(&ctx.http, builder).await {
+                error!("Error editing interaction response: {e:?}");
+            }
+        } else {
+            let builder = CreateInteractionResponseFollowup::new().embed(embed);
+            if let Err(e) = command.create_followup(&ctx.http, builder).await {
+                error!("Error sending follow-up message: {e:?}");
+            }
+        }
+    }
+
+    async fn handle_pagination_button(&self, ctx: &Context, component: &ComponentInteraction) {
+        let message_id = component.message.id;
+        let mut pagination = self.pagination.lock().await;
+
+        let Some((_, state)) = pagination.get_mut(&message_id) else {
+            // The state has already expired (e.g. after a restart, or swept past its TTL); just
+            // ack the click.
+            let _ = component
+                .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+                .await;
+            return;
+        };
+
+        match component.data.custom_id.as_str() {
+            pagination::CUSTOM_ID_FIRST => state.go_first(),
+            pagination::CUSTOM_ID_PREV => state.go_prev(),
+            pagination::CUSTOM_ID_NEXT => state.go_next(),
+            pagination::CUSTOM_ID_LAST => state.go_last(),
+            other => {
+                debug!("Unhandled pagination custom_id: {other}");
+                return;
+            }
+        }
+
+        let embed = pagination::build_page_embed(state);
+        let row = pagination::build_pagination_row(state);
+
+        let builder = CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .components(vec![row]),
+        );
+        if let Err(e) = component.create_response(&ctx.http, builder).await {
+            error!("Error updating paginated message: {e:?}");
+        }
+    }
 }
 
 #[async_trait]
@@ -33,33 +568,60 @@ impl EventHandler for Bot {
         let mention_regex = Regex::new(r"<@!?\d+>").unwrap();
         let cleaned_content = mention_regex.replace_all(&content, "").trim().to_string();
 
-        // Parse range specifications
-        let specs = parse_range_specifications(&cleaned_content);
-        debug!("Input: '{cleaned_content}', Parsed specs: {specs:?}");
+        // A trailing "embed" token switches the reply from the paginated text viewer to a
+        // structured, per-post embed (mirrors the /fetch embed option).
+        let mut tokens: Vec<&str> = cleaned_content.split_whitespace().collect();
+        let use_embed = tokens.last() == Some(&"embed");
+        if use_embed {
+            tokens.pop();
+        }
+        let range_input = tokens.join(" ");
 
-        if specs.is_empty() {
-            if let Err(e) = msg
-                .reply(
-                    &ctx.http,
-                    "使い方: @fetch-post 123 または @fetch-post 123-128 または @fetch-post 123- または @fetch-post 123,124-128 または @fetch-post ^322,?324-326,?^325",
-                )
-                .await
-            {
-                error!("Error sending message: {e:?}");
+        // Parse range specifications
+        let specs = match parse_range_specifications_checked(&range_input) {
+            Ok(specs) if specs.is_empty() => {
+                if let Err(e) = msg
+                    .reply(
+                        &ctx.http,
+                        "使い方: @fetch-post 123 または @fetch-post 123-128 または @fetch-post 123- または @fetch-post 123,124-128 または @fetch-post ^322,?324-326,?^325\nまたは /fetch range:<範囲> を使用してください。",
+                    )
+                    .await
+                {
+                    error!("Error sending message: {e:?}");
+                }
+                return;
             }
-            return;
-        }
+            Ok(specs) => specs,
+            Err(errors) => {
+                let detail = errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if let Err(e) = msg
+                    .reply(
+                        &ctx.http,
+                        format!("範囲の指定を解釈できませんでした: {detail}"),
+                    )
+                    .await
+                {
+                    error!("Error sending message: {e:?}");
+                }
+                return;
+            }
+        };
+        debug!("Input: '{range_input}', embed={use_embed}, Parsed specs: {specs:?}");
 
         // Check if any spec requires max post number
         let needs_max = specs.iter().any(|spec| {
             debug!("Checking spec {spec:?} for needs_max");
             matches!(
                 spec,
-                RangeSpec::IncludeFrom(_)
+                RangeSpec::IncludeFrom(_, _)
                     | RangeSpec::ExcludeFrom(_)
-                    | RangeSpec::RelativeInclude(_, _, _)
-                    | RangeSpec::RelativeExclude(_, _, _)
-                    | RangeSpec::RelativeIncludeFrom(_, _)
+                    | RangeSpec::RelativeInclude(_, _, _, _, _)
+                    | RangeSpec::RelativeExclude(_, _, _, _)
+                    | RangeSpec::RelativeIncludeFrom(_, _, _)
                     | RangeSpec::RelativeExcludeFrom(_, _)
             )
         });
@@ -86,7 +648,7 @@ impl EventHandler for Bot {
             0 // Won't be used if not needed
         };
 
-        let post_numbers = calculate_post_numbers(specs, max_post_number);
+        let post_numbers = calculate_post_numbers_with_extra(specs, max_post_number, &[]);
         debug!("Calculated post numbers: {post_numbers:?}");
 
         if post_numbers.is_empty() {
@@ -109,54 +671,59 @@ impl EventHandler for Bot {
                     {
                         error!("Error sending message: {e:?}");
                     }
-                } else {
-                    // Send posts with images if they have oekaki_id
-                    let mut current_message = String::new();
-
+                } else if use_embed {
+                    // Structured mode: one embed per post, with the oekaki image (if any)
+                    // inlined directly instead of trailing as a separate message.
                     for post in posts.iter() {
-                        let post_text = format!("{post}");
-
-                        // Check if adding this post would exceed Discord's limit
-                        if !current_message.is_empty()
-                            && current_message.len() + post_text.len() > 1800
-                        {
-                            // Send the current batch
-                            if let Err(e) = msg.reply(&ctx.http, &current_message).await {
-                                error!("Error sending message: {e:?}");
-                            }
-                            current_message.clear();
+                        let post_embed = render::render_post_embed(post, &self.image_url_prefix);
+                        let builder = CreateMessage::new()
+                            .reference_message(&msg)
+                            .embed(post_embed);
+
+                        if let Err(e) = msg.channel_id.send_message(&ctx.http, builder).await {
+                            error!("Error sending embed message: {e:?}");
                         }
+                    }
+                } else {
+                    // Render posts into a paginated embed viewer instead of flushing plain-text
+                    // chunks, so a large range stays browsable behind a handful of buttons.
+                    let rendered_posts: Vec<String> =
+                        posts.iter().map(|post| format!("{post}")).collect();
+                    let pages = pagination::paginate_posts(&rendered_posts, 1800);
+                    let state = PaginationState::new(pages);
+                    let embed = pagination::build_page_embed(&state);
 
-                        current_message.push_str(&post_text);
+                    let mut builder = CreateMessage::new().reference_message(&msg).embed(embed);
+                    if state.pages.len() > 1 {
+                        builder =
+                            builder.components(vec![pagination::build_pagination_row(&state)]);
+                    }
 
-                        // Send image if oekaki_id exists
-                        if let Some(oekaki_id) = post.oekaki_id {
-                            // Send current text if any
-                            if !current_message.is_empty() {
-                                if let Err(e) = msg.reply(&ctx.http, &current_message).await {
-                                    error!("Error sending message: {e:?}");
-                                }
-                                current_message.clear();
-                            }
+                    match msg.channel_id.send_message(&ctx.http, builder).await {
+                        Ok(sent) if state.pages.len() > 1 => {
+                            self.evict_stale_pagination().await;
+                            self.pagination
+                                .lock()
+                                .await
+                                .insert(sent.id, (Instant::now(), state));
+                        }
+                        Ok(_) => {}
+                        Err(e) => error!("Error sending message: {e:?}"),
+                    }
 
-                            // Send image as embed
+                    // Oekaki images are sent as their own follow-up embeds, outside of pagination.
+                    for post in posts.iter() {
+                        if let Some(oekaki_id) = post.oekaki_id {
                             let image_url = format!("{}{}.png", self.image_url_prefix, oekaki_id);
                             let builder = CreateMessage::new()
                                 .reference_message(&msg)
                                 .embed(CreateEmbed::new().image(image_url));
 
                             if let Err(e) = msg.channel_id.send_message(&ctx.http, builder).await {
-                                eprintln!("Error sending image: {e:?}");
+                                error!("Error sending image: {e:?}");
                             }
                         }
                     }
-
-                    // Send any remaining text
-                    if !current_message.is_empty() {
-                        if let Err(e) = msg.reply(&ctx.http, current_message).await {
-                            error!("Error sending message: {e:?}");
-                        }
-                    }
                 }
             }
             Err(e) => {
@@ -171,8 +738,83 @@ impl EventHandler for Bot {
         }
     }
 
-    async fn ready(&self, _: Context, ready: Ready) {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::Command(command) => match command.data.name.as_str() {
+                "fetch" => self.handle_fetch_command(&ctx, &command).await,
+                "subscribe" => self.handle_subscribe_command(&ctx, &command).await,
+                "unsubscribe" => self.handle_unsubscribe_command(&ctx, &command).await,
+                "similar" => self.handle_similar_command(&ctx, &command).await,
+                "search" => self.handle_search_command(&ctx, &command).await,
+                other => debug!("Unhandled slash command: {other}"),
+            },
+            Interaction::Component(component) => {
+                self.handle_pagination_button(&ctx, &component).await
+            }
+            _ => {}
+        }
+    }
+
+    async fn ready(&self, ctx: Context, ready: Ready) {
         info!("{} is connected!", ready.user.name);
+
+        let fetch_command = CreateCommand::new("fetch")
+            .description("指定した範囲または日時のレスを取得します")
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "range",
+                "取得範囲 (例: 123-128, ^321, ?324)",
+            ))
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "date",
+                "取得する日時範囲 (例: 2024-01-01, 2024-01-01..2024-02-01, -7d, -24h)",
+            ))
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "embed",
+                "埋め込み形式で表示する",
+            ));
+
+        if let Err(e) = Command::create_global_command(&ctx.http, fetch_command).await {
+            error!("Error registering /fetch command: {e:?}");
+        }
+
+        let subscribe_command = CreateCommand::new("subscribe")
+            .description("このチャンネルを新着レスの通知登録に追加します");
+        if let Err(e) = Command::create_global_command(&ctx.http, subscribe_command).await {
+            error!("Error registering /subscribe command: {e:?}");
+        }
+
+        let unsubscribe_command = CreateCommand::new("unsubscribe")
+            .description("このチャンネルの新着レス通知登録を解除します");
+        if let Err(e) = Command::create_global_command(&ctx.http, unsubscribe_command).await {
+            error!("Error registering /unsubscribe command: {e:?}");
+        }
+
+        let similar_command = CreateCommand::new("similar")
+            .description("指定したレスのお絵かきと似ている絵を探します")
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "post_number",
+                    "基準にするレス番号",
+                )
+                .required(true),
+            );
+        if let Err(e) = Command::create_global_command(&ctx.http, similar_command).await {
+            error!("Error registering /similar command: {e:?}");
+        }
+
+        let search_command = CreateCommand::new("search")
+            .description("キーワードを含むレスを検索します")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "keyword", "検索キーワード")
+                    .required(true),
+            );
+        if let Err(e) = Command::create_global_command(&ctx.http, search_command).await {
+            error!("Error registering /search command: {e:?}");
+        }
     }
 }
 
@@ -188,11 +830,12 @@ async fn main() -> Result<()> {
     let image_url_prefix =
         env::var("IMAGE_URL_PREFIX").expect("Expected IMAGE_URL_PREFIX in environment");
 
-    let pool = PgPool::connect(&database_url).await?;
+    let pool = Arc::new(PgPool::connect(&database_url).await?);
 
     let bot = Bot {
-        pool: Arc::new(pool),
-        image_url_prefix,
+        pool: pool.clone(),
+        image_url_prefix: image_url_prefix.clone(),
+        pagination: Arc::new(Mutex::new(HashMap::new())),
     };
 
     let intents = GatewayIntents::GUILD_MESSAGES
@@ -204,6 +847,12 @@ async fn main() -> Result<()> {
         .await
         .expect("Error creating client");
 
+    tokio::spawn(subscriptions::run_watcher(
+        client.http.clone(),
+        pool.clone(),
+    ));
+    tokio::spawn(phash::run_backfill(pool, image_url_prefix));
+
     if let Err(why) = client.start().await {
         error!("Client error: {why:?}");
     }
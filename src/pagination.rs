@@ -0,0 +1,96 @@
+use serenity::builder::{CreateActionRow, CreateButton, CreateEmbed};
+use serenity::model::application::ButtonStyle;
+
+/// How the result of a single mention (`message`) invocation is split and navigated once it has
+/// been rendered into Discord's message components. `/fetch` replies are not paginated.
+pub struct PaginationState {
+    pub pages: Vec<String>,
+    pub current: usize,
+}
+
+impl PaginationState {
+    pub fn new(pages: Vec<String>) -> Self {
+        Self { pages, current: 0 }
+    }
+
+    pub fn go_first(&mut self) {
+        self.current = 0;
+    }
+
+    pub fn go_last(&mut self) {
+        self.current = self.pages.len().saturating_sub(1);
+    }
+
+    pub fn go_prev(&mut self) {
+        self.current = self.current.saturating_sub(1);
+    }
+
+    pub fn go_next(&mut self) {
+        self.current = (self.current + 1).min(self.pages.len().saturating_sub(1));
+    }
+}
+
+/// Packs rendered posts into pages of at most `max_page_len` bytes. A post is never split across
+/// pages, so a single post longer than `max_page_len` gets a page of its own that exceeds it.
+pub fn paginate_posts(rendered_posts: &[String], max_page_len: usize) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut current = String::new();
+
+    for post_text in rendered_posts {
+        if !current.is_empty() && current.len() + post_text.len() > max_page_len {
+            pages.push(std::mem::take(&mut current));
+        }
+        current.push_str(post_text);
+    }
+
+    if !current.is_empty() {
+        pages.push(current);
+    }
+
+    pages
+}
+
+pub const CUSTOM_ID_FIRST: &str = "fetch_page_first";
+pub const CUSTOM_ID_PREV: &str = "fetch_page_prev";
+pub const CUSTOM_ID_NEXT: &str = "fetch_page_next";
+pub const CUSTOM_ID_LAST: &str = "fetch_page_last";
+
+pub fn build_page_embed(state: &PaginationState) -> CreateEmbed {
+    let page_text = state
+        .pages
+        .get(state.current)
+        .map(String::as_str)
+        .unwrap_or_default();
+
+    CreateEmbed::new()
+        .description(page_text)
+        .footer(serenity::builder::CreateEmbedFooter::new(format!(
+            "ページ {}/{}",
+            state.current + 1,
+            state.pages.len()
+        )))
+}
+
+pub fn build_pagination_row(state: &PaginationState) -> CreateActionRow {
+    let at_first = state.current == 0;
+    let at_last = state.current + 1 >= state.pages.len();
+
+    CreateActionRow::Buttons(vec![
+        CreateButton::new(CUSTOM_ID_FIRST)
+            .label("⏮")
+            .style(ButtonStyle::Secondary)
+            .disabled(at_first),
+        CreateButton::new(CUSTOM_ID_PREV)
+            .label("◀")
+            .style(ButtonStyle::Primary)
+            .disabled(at_first),
+        CreateButton::new(CUSTOM_ID_NEXT)
+            .label("▶")
+            .style(ButtonStyle::Primary)
+            .disabled(at_last),
+        CreateButton::new(CUSTOM_ID_LAST)
+            .label("⏭")
+            .style(ButtonStyle::Secondary)
+            .disabled(at_last),
+    ])
+}
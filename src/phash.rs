@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use bbs_fetch_post_discord_bot::{get_unhashed_oekaki_posts, store_oekaki_hash};
+use image::imageops::FilterType;
+use log::{debug, error};
+use sqlx::postgres::PgPool;
+
+/// dHash resizes down to 9x8 so each of the 8 rows yields 8 left/right comparisons, packing
+/// neatly into a 64-bit fingerprint.
+const HASH_WIDTH: u32 = 9;
+const HASH_HEIGHT: u32 = 8;
+
+const BACKFILL_INTERVAL: Duration = Duration::from_secs(300);
+const BACKFILL_BATCH: i64 = 20;
+
+/// Computes the difference-hash of an already-downloaded image: grayscale, resize to 9x8, then
+/// for each row set a bit wherever a pixel is brighter than its right neighbor.
+pub fn dhash_from_bytes(bytes: &[u8]) -> Result<u64> {
+    let image = image::load_from_memory(bytes)?;
+    let small = image
+        .resize_exact(HASH_WIDTH, HASH_HEIGHT, FilterType::Lanczos3)
+        .to_luma8();
+
+    let mut hash: u64 = 0;
+    for y in 0..HASH_HEIGHT {
+        for x in 0..HASH_WIDTH - 1 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+
+    Ok(hash)
+}
+
+/// Downloads the image at `image_url` and returns its dHash fingerprint.
+pub async fn fetch_and_hash(image_url: &str) -> Result<u64> {
+    let bytes = reqwest::get(image_url).await?.bytes().await?;
+    dhash_from_bytes(&bytes)
+}
+
+/// Number of differing bits between two fingerprints; lower means more visually similar.
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Background task spawned from `main`: on every tick, hashes a batch of oekaki posts that don't
+/// have a cached fingerprint yet, so `/similar` has a populated corpus to compare against instead
+/// of just the handful of posts users have directly queried.
+pub async fn run_backfill(pool: Arc<PgPool>, image_url_prefix: String) {
+    let mut ticker = tokio::time::interval(BACKFILL_INTERVAL);
+
+    loop {
+        ticker.tick().await;
+
+        let pending = match get_unhashed_oekaki_posts(&pool, BACKFILL_BATCH).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                error!("backfill: error loading unhashed oekaki posts: {e:?}");
+                continue;
+            }
+        };
+
+        let hashed = pending.len();
+        for (post_no, oekaki_id) in pending {
+            let image_url = format!("{image_url_prefix}{oekaki_id}.png");
+            let hash = match fetch_and_hash(&image_url).await {
+                Ok(hash) => hash as i64,
+                Err(e) => {
+                    error!("backfill: error hashing oekaki image for post {post_no}: {e:?}");
+                    continue;
+                }
+            };
+
+            if let Err(e) = store_oekaki_hash(&pool, post_no, hash).await {
+                error!("backfill: error storing oekaki hash for post {post_no}: {e:?}");
+            }
+        }
+
+        debug!("backfill: poll complete, processed {hashed} posts");
+    }
+}
@@ -0,0 +1,54 @@
+use bbs_fetch_post_discord_bot::Res;
+use serenity::builder::CreateEmbed;
+
+/// Discord's blurple, used as the embed's colored sidebar.
+const ACCENT_COLOUR: u32 = 0x5865F2;
+
+/// Discord embed field values are capped at 1024 characters.
+const MAX_FIELD_LEN: usize = 1024;
+
+/// Renders a single post as a structured embed with the post number as the title, author,
+/// timestamp and ID broken out into their own fields, and the oekaki image (if any) inlined
+/// directly rather than sent as a separate trailing image message.
+pub fn render_post_embed(post: &Res, image_url_prefix: &str) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title(format!("#{}", post.no))
+        .colour(ACCENT_COLOUR)
+        .field(
+            "投稿者",
+            non_empty_field(&post.name_and_trip, "（名無し）"),
+            true,
+        )
+        .field("日時", &post.datetime_text, true)
+        .field("ID", non_empty_field(&post.id, "（IDなし）"), true)
+        .field(
+            "本文",
+            truncate_field(non_empty_field(&post.main_text, "（本文なし）")),
+            false,
+        );
+
+    if let Some(oekaki_id) = post.oekaki_id {
+        embed = embed.image(format!("{image_url_prefix}{oekaki_id}.png"));
+    }
+
+    embed
+}
+
+/// Discord rejects embed fields with an empty value, so substitute a placeholder rather than
+/// let the whole embed send fail (e.g. an oekaki-only post has no `main_text`).
+fn non_empty_field<'a>(text: &'a str, placeholder: &'a str) -> &'a str {
+    if text.is_empty() {
+        placeholder
+    } else {
+        text
+    }
+}
+
+fn truncate_field(text: &str) -> String {
+    if text.chars().count() <= MAX_FIELD_LEN {
+        text.to_string()
+    } else {
+        let truncated: String = text.chars().take(MAX_FIELD_LEN - 1).collect();
+        format!("{truncated}…")
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bbs_fetch_post_discord_bot::{
+    get_max_post_number, get_res_by_numbers, get_subscriptions, update_subscription_cursor,
+};
+use log::{debug, error};
+use serenity::builder::CreateMessage;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use sqlx::postgres::PgPool;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Background task spawned from `main`: polls for new posts past each subscription's stored
+/// cursor and forwards them to that channel.
+pub async fn run_watcher(http: Arc<Http>, pool: Arc<PgPool>) {
+    let mut ticker = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        ticker.tick().await;
+
+        let max = match get_max_post_number(&pool).await {
+            Ok(max) => max,
+            Err(e) => {
+                error!("watcher: error getting max post number: {e:?}");
+                continue;
+            }
+        };
+
+        let subscriptions = match get_subscriptions(&pool).await {
+            Ok(subs) => subs,
+            Err(e) => {
+                error!("watcher: error loading subscriptions: {e:?}");
+                continue;
+            }
+        };
+
+        for sub in subscriptions {
+            if max <= sub.last_seen {
+                continue;
+            }
+
+            let new_numbers: Vec<i32> = (sub.last_seen + 1..=max).collect();
+            let posts = match get_res_by_numbers(&pool, new_numbers).await {
+                Ok(posts) => posts,
+                Err(e) => {
+                    error!("watcher: error fetching new posts: {e:?}");
+                    continue;
+                }
+            };
+
+            let channel_id = ChannelId::new(sub.channel_id as u64);
+            let mut delivered_up_to = sub.last_seen;
+            for post in &posts {
+                let builder = CreateMessage::new().content(format!("{post}"));
+                if let Err(e) = channel_id.send_message(&http, builder).await {
+                    error!(
+                        "watcher: error posting new res to channel {}: {e:?}",
+                        sub.channel_id
+                    );
+                    break;
+                }
+                delivered_up_to = post.no;
+            }
+
+            if delivered_up_to > sub.last_seen {
+                if let Err(e) =
+                    update_subscription_cursor(&pool, sub.channel_id, delivered_up_to).await
+                {
+                    error!(
+                        "watcher: error updating cursor for channel {}: {e:?}",
+                        sub.channel_id
+                    );
+                }
+            }
+        }
+
+        debug!("watcher: poll complete, max_post_number={max}");
+    }
+}